@@ -0,0 +1,47 @@
+use tower_sessions_core::session::Record;
+
+use crate::SqlxStoreError;
+
+/// A codec that encodes and decodes session [`Record`]s to and from the bytes
+/// persisted by a store.
+///
+/// The store holds the codec behind an [`Arc`](std::sync::Arc), so
+/// implementors needn't implement [`Clone`]. Implementations should map their
+/// internal errors onto [`SqlxStoreError::Encode`] and
+/// [`SqlxStoreError::Decode`].
+pub trait SessionCodec: std::fmt::Debug + Send + Sync {
+    /// Encode a record into its on-the-wire byte representation.
+    fn encode(&self, record: &Record) -> Result<Vec<u8>, SqlxStoreError>;
+
+    /// Decode a record from its on-the-wire byte representation.
+    fn decode(&self, data: &[u8]) -> Result<Record, SqlxStoreError>;
+}
+
+/// The default codec, encoding records as MessagePack via [`rmp_serde`].
+#[derive(Clone, Debug, Default)]
+pub struct MessagePackCodec;
+
+impl SessionCodec for MessagePackCodec {
+    fn encode(&self, record: &Record) -> Result<Vec<u8>, SqlxStoreError> {
+        rmp_serde::to_vec(record).map_err(|err| SqlxStoreError::Encode(Box::new(err)))
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Record, SqlxStoreError> {
+        rmp_serde::from_slice(data).map_err(|err| SqlxStoreError::Decode(Box::new(err)))
+    }
+}
+
+/// A codec encoding records as JSON via [`serde_json`], for a
+/// human-inspectable wire format.
+#[derive(Clone, Debug, Default)]
+pub struct JsonCodec;
+
+impl SessionCodec for JsonCodec {
+    fn encode(&self, record: &Record) -> Result<Vec<u8>, SqlxStoreError> {
+        serde_json::to_vec(record).map_err(|err| SqlxStoreError::Encode(Box::new(err)))
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Record, SqlxStoreError> {
+        serde_json::from_slice(data).map_err(|err| SqlxStoreError::Decode(Box::new(err)))
+    }
+}
@@ -0,0 +1,39 @@
+use tower_sessions_core::session_store;
+
+/// An error type for SQLx stores.
+#[derive(thiserror::Error, Debug)]
+pub enum SqlxStoreError {
+    /// A variant to map `sqlx` errors.
+    #[error(transparent)]
+    Sqlx(#[from] sqlx::Error),
+
+    /// A variant to map codec encoding errors.
+    #[error("failed to encode session record: {0}")]
+    Encode(Box<dyn std::error::Error + Send + Sync>),
+
+    /// A variant to map codec decoding errors.
+    #[error("failed to decode session record: {0}")]
+    Decode(Box<dyn std::error::Error + Send + Sync>),
+
+    /// A variant returned when a provided schema name is not a valid
+    /// identifier.
+    #[error("invalid schema name: {0}")]
+    InvalidSchemaName(String),
+
+    /// A variant returned when a provided table name is not a valid identifier.
+    #[error("invalid table name: {0}")]
+    InvalidTableName(String),
+}
+
+impl From<SqlxStoreError> for session_store::Error {
+    fn from(err: SqlxStoreError) -> Self {
+        match err {
+            SqlxStoreError::Sqlx(inner) => session_store::Error::Backend(inner.to_string()),
+            SqlxStoreError::Encode(inner) => session_store::Error::Encode(inner.to_string()),
+            SqlxStoreError::Decode(inner) => session_store::Error::Decode(inner.to_string()),
+            err @ (SqlxStoreError::InvalidSchemaName(_) | SqlxStoreError::InvalidTableName(_)) => {
+                session_store::Error::Backend(err.to_string())
+            }
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use async_trait::async_trait;
 use sqlx::MySqlPool;
 use time::OffsetDateTime;
@@ -6,7 +8,10 @@ use tower_sessions_core::{
     session_store, ExpiredDeletion, SessionStore,
 };
 
-use crate::SqlxStoreError;
+use crate::{
+    codec::{MessagePackCodec, SessionCodec},
+    SqlxStoreError,
+};
 
 /// A MySQL session store.
 #[derive(Clone, Debug)]
@@ -14,6 +19,17 @@ pub struct MySqlStore {
     pool: MySqlPool,
     schema_name: String,
     table_name: String,
+    create_schema: bool,
+    codec: Arc<dyn SessionCodec>,
+}
+
+/// Ensure that an identifier only contains characters that are safe to
+/// interpolate into a backtick-quoted SQL identifier.
+fn is_valid_identifier(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
 }
 
 impl MySqlStore {
@@ -35,7 +51,109 @@ impl MySqlStore {
             pool,
             schema_name: "tower_sessions".to_string(),
             table_name: "session".to_string(),
+            create_schema: true,
+            codec: Arc::new(MessagePackCodec),
+        }
+    }
+
+    /// Set the codec used to encode and decode session records.
+    ///
+    /// Defaults to [`MessagePackCodec`]. Use this to select an alternative wire
+    /// format, such as [`JsonCodec`](crate::codec::JsonCodec), to match rows
+    /// written by another store or for human-inspectable debugging.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use tower_sessions_sqlx::{codec::JsonCodec, sqlx::MySqlPool, MySqlStore};
+    ///
+    /// # tokio_test::block_on(async {
+    /// let database_url = std::option_env!("DATABASE_URL").unwrap();
+    /// let pool = MySqlPool::connect(database_url).await.unwrap();
+    /// let session_store = MySqlStore::new(pool).with_codec(JsonCodec);
+    /// # })
+    /// ```
+    pub fn with_codec(mut self, codec: impl SessionCodec + 'static) -> Self {
+        self.codec = Arc::new(codec);
+        self
+    }
+
+    /// Set the schema name used for the session table.
+    ///
+    /// The name is validated to only contain `[A-Za-z0-9_-]` so that it is
+    /// safe to interpolate into the backtick-quoted queries this store builds.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use tower_sessions_sqlx::{sqlx::MySqlPool, MySqlStore};
+    ///
+    /// # tokio_test::block_on(async {
+    /// let database_url = std::option_env!("DATABASE_URL").unwrap();
+    /// let pool = MySqlPool::connect(database_url).await.unwrap();
+    /// let session_store = MySqlStore::new(pool).with_schema_name("my_schema").unwrap();
+    /// # })
+    /// ```
+    pub fn with_schema_name(
+        mut self,
+        schema_name: impl Into<String>,
+    ) -> Result<Self, SqlxStoreError> {
+        let schema_name = schema_name.into();
+        if !is_valid_identifier(&schema_name) {
+            return Err(SqlxStoreError::InvalidSchemaName(schema_name));
+        }
+        self.schema_name = schema_name;
+        Ok(self)
+    }
+
+    /// Set the table name used for the session store.
+    ///
+    /// The name is validated to only contain `[A-Za-z0-9_-]` so that it is
+    /// safe to interpolate into the backtick-quoted queries this store builds.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use tower_sessions_sqlx::{sqlx::MySqlPool, MySqlStore};
+    ///
+    /// # tokio_test::block_on(async {
+    /// let database_url = std::option_env!("DATABASE_URL").unwrap();
+    /// let pool = MySqlPool::connect(database_url).await.unwrap();
+    /// let session_store = MySqlStore::new(pool).with_table_name("my_sessions").unwrap();
+    /// # })
+    /// ```
+    pub fn with_table_name(
+        mut self,
+        table_name: impl Into<String>,
+    ) -> Result<Self, SqlxStoreError> {
+        let table_name = table_name.into();
+        if !is_valid_identifier(&table_name) {
+            return Err(SqlxStoreError::InvalidTableName(table_name));
         }
+        self.table_name = table_name;
+        Ok(self)
+    }
+
+    /// Control whether [`migrate`](Self::migrate) issues a `create schema`
+    /// statement.
+    ///
+    /// Disable this when the schema already exists and the connection user
+    /// lacks the privileges to create one.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use tower_sessions_sqlx::{sqlx::MySqlPool, MySqlStore};
+    ///
+    /// # tokio_test::block_on(async {
+    /// let database_url = std::option_env!("DATABASE_URL").unwrap();
+    /// let pool = MySqlPool::connect(database_url).await.unwrap();
+    /// let session_store = MySqlStore::new(pool).with_schema_creation(false);
+    /// # })
+    /// ```
+    pub fn with_schema_creation(mut self, create_schema: bool) -> Self {
+        self.create_schema = create_schema;
+        self
     }
 
     /// Migrate the session schema.
@@ -55,11 +173,13 @@ impl MySqlStore {
     pub async fn migrate(&self) -> sqlx::Result<()> {
         let mut tx = self.pool.begin().await?;
 
-        let create_schema_query = format!(
-            "create schema if not exists {schema_name}",
-            schema_name = self.schema_name,
-        );
-        sqlx::query(&create_schema_query).execute(&mut *tx).await?;
+        if self.create_schema {
+            let create_schema_query = format!(
+                "create schema if not exists `{schema_name}`",
+                schema_name = self.schema_name,
+            );
+            sqlx::query(&create_schema_query).execute(&mut *tx).await?;
+        }
 
         let create_table_query = format!(
             r#"
@@ -79,6 +199,110 @@ impl MySqlStore {
 
         Ok(())
     }
+
+    /// Delete every session row in the configured table.
+    ///
+    /// This is useful to force a global logout, for example when a signing
+    /// secret is rotated and all existing sessions must be invalidated.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use tower_sessions_sqlx::{sqlx::MySqlPool, MySqlStore};
+    ///
+    /// # tokio_test::block_on(async {
+    /// let database_url = std::option_env!("DATABASE_URL").unwrap();
+    /// let pool = MySqlPool::connect(database_url).await.unwrap();
+    /// let session_store = MySqlStore::new(pool);
+    /// session_store.clear().await.unwrap();
+    /// # })
+    /// ```
+    pub async fn clear(&self) -> session_store::Result<()> {
+        let query = format!(
+            r#"truncate table `{schema_name}`.`{table_name}`"#,
+            schema_name = self.schema_name,
+            table_name = self.table_name
+        );
+        sqlx::query(&query)
+            .execute(&self.pool)
+            .await
+            .map_err(SqlxStoreError::Sqlx)?;
+
+        Ok(())
+    }
+
+    /// Count the number of live (non-expired) sessions in the configured table.
+    ///
+    /// This is handy for exposing a metrics gauge of active sessions.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use tower_sessions_sqlx::{sqlx::MySqlPool, MySqlStore};
+    ///
+    /// # tokio_test::block_on(async {
+    /// let database_url = std::option_env!("DATABASE_URL").unwrap();
+    /// let pool = MySqlPool::connect(database_url).await.unwrap();
+    /// let session_store = MySqlStore::new(pool);
+    /// let active = session_store.count().await.unwrap();
+    /// # })
+    /// ```
+    pub async fn count(&self) -> session_store::Result<i64> {
+        let query = format!(
+            r#"
+            select count(*) from `{schema_name}`.`{table_name}`
+            where expiry_date > ?
+            "#,
+            schema_name = self.schema_name,
+            table_name = self.table_name
+        );
+        let (count,): (i64,) = sqlx::query_as(&query)
+            .bind(OffsetDateTime::now_utc())
+            .fetch_one(&self.pool)
+            .await
+            .map_err(SqlxStoreError::Sqlx)?;
+
+        Ok(count)
+    }
+
+    /// This function will keep running indefinitely, deleting expired rows and
+    /// then waiting for the specified period before deleting again.
+    ///
+    /// Errors from individual deletion passes are logged via [`tracing`] and do
+    /// not stop the loop, so the returned future never resolves. Generally this
+    /// will be used as a [`tokio::spawn`]'d task.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use std::time::Duration;
+    ///
+    /// use tower_sessions_sqlx::{sqlx::MySqlPool, MySqlStore};
+    ///
+    /// # tokio_test::block_on(async {
+    /// let database_url = std::option_env!("DATABASE_URL").unwrap();
+    /// let pool = MySqlPool::connect(database_url).await.unwrap();
+    /// let session_store = MySqlStore::new(pool);
+    /// tokio::task::spawn(
+    ///     session_store
+    ///         .clone()
+    ///         .continuously_delete_expired(Duration::from_secs(60)),
+    /// );
+    /// # })
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub async fn continuously_delete_expired(
+        self,
+        period: tokio::time::Duration,
+    ) -> session_store::Result<()> {
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            if let Err(err) = self.delete_expired().await {
+                tracing::error!(err = %err, "failed to delete expired sessions");
+            }
+        }
+    }
 }
 
 #[async_trait]
@@ -116,7 +340,7 @@ impl SessionStore for MySqlStore {
         );
         sqlx::query(&query)
             .bind(&record.id.to_string())
-            .bind(rmp_serde::to_vec(&record).map_err(SqlxStoreError::Encode)?)
+            .bind(self.codec.encode(record)?)
             .bind(record.expiry_date)
             .execute(&self.pool)
             .await
@@ -142,9 +366,7 @@ impl SessionStore for MySqlStore {
             .map_err(SqlxStoreError::Sqlx)?;
 
         if let Some((data,)) = data {
-            Ok(Some(
-                rmp_serde::from_slice(&data).map_err(SqlxStoreError::Decode)?,
-            ))
+            Ok(Some(self.codec.decode(&data)?))
         } else {
             Ok(None)
         }
@@ -165,3 +387,29 @@ impl SessionStore for MySqlStore {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::is_valid_identifier;
+
+    #[test]
+    fn accepts_typical_identifiers() {
+        assert!(is_valid_identifier("tower_sessions"));
+        assert!(is_valid_identifier("session"));
+        assert!(is_valid_identifier("my-schema"));
+        assert!(is_valid_identifier("Sessions_2"));
+    }
+
+    #[test]
+    fn rejects_empty_identifier() {
+        assert!(!is_valid_identifier(""));
+    }
+
+    #[test]
+    fn rejects_injection_characters() {
+        assert!(!is_valid_identifier("session`; drop table users; --"));
+        assert!(!is_valid_identifier("schema.table"));
+        assert!(!is_valid_identifier("with space"));
+        assert!(!is_valid_identifier("quote\"name"));
+    }
+}